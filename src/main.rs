@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::fs::OpenOptions;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitCode};
 use std::rc::Rc;
@@ -14,6 +14,19 @@ const DEFAULT_COLOR: colored::Color = colored::Color::White;
 const ERROR_COLOR: colored::Color = colored::Color::Red;
 const TIME_COLOR: colored::Color = colored::Color::BrightBlack;
 
+/// Colors cycled through to distinguish workers' labels in interleaved output.
+const WORKER_COLORS: &[colored::Color] = &[
+    colored::Color::Cyan,
+    colored::Color::Magenta,
+    colored::Color::Yellow,
+    colored::Color::Blue,
+    colored::Color::Green,
+];
+
+/// Exit code used when a child is killed because [`Args::timeout`] elapsed,
+/// distinct from the generic fallback used when a child's own exit code is unavailable.
+const TIMEOUT_EXIT_CODE: u8 = 124;
+
 #[derive(Debug, Parser)]
 struct Args {
     #[arg(long("ts"), default_value("off"))]
@@ -24,10 +37,67 @@ struct Args {
     #[arg(long, conflicts_with("timestamps"), conflicts_with("color"))]
     plain: bool,
 
+    /// Terminate any still-running worker after this long, e.g. `30s`/`2m`.
+    #[arg(long)]
+    timeout: Option<Timeout>,
+
+    /// When `--timeout` fires, how long to wait after SIGTERM before escalating to SIGKILL.
+    #[arg(long, default_value("10s"))]
+    grace_period: Timeout,
+
+    /// Don't forward this process's stdin to the child.
+    /// Only meaningful with a single worker command; ignored otherwise.
+    /// Forwarding is also skipped automatically when stdin is a terminal.
+    #[arg(long)]
+    no_stdin: bool,
+
+    /// Structured format written to the log file. The terminal always stays
+    /// human-readable text regardless of this setting.
+    #[arg(long, default_value("text"))]
+    format: FormatSpec,
+
     log_file: PathBuf,
 
-    command: String,
-    args: Vec<String>,
+    /// One or more worker commands to run concurrently.
+    ///
+    /// A single command needs no separator: `t3 log.txt ping localhost`.
+    /// Multiple commands are each their own `--`-delimited group, optionally
+    /// labelled with a leading `name=`:
+    /// `t3 log.txt -- server=npm run server -- client=npm run client`.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+/// A duration parsed from a simple `<number><unit>` string, e.g. `30s`, `2m`, `1h`.
+#[derive(Debug, Clone, Copy)]
+struct Timeout(std::time::Duration);
+
+impl std::str::FromStr for Timeout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split_at = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+            format!("missing time unit in duration '{s}', expected 's', 'm' or 'h'")
+        })?;
+        let (value, unit) = s.split_at(split_at);
+
+        let value: u64 = value
+            .parse()
+            .map_err(|_| format!("invalid duration '{s}'"))?;
+
+        let secs = match unit {
+            "s" => value,
+            "m" => value * 60,
+            "h" => value * 3600,
+            _ => {
+                return Err(format!(
+                    "unknown time unit '{unit}', expected 's', 'm' or 'h'"
+                ))
+            }
+        };
+
+        Ok(Timeout(std::time::Duration::from_secs(secs)))
+    }
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -44,6 +114,13 @@ enum ColorSpec {
     Never,
 }
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum FormatSpec {
+    Text,
+    Json,
+    Logfmt,
+}
+
 fn main() -> Result<ExitCode> {
     let mut args = Args::try_parse()?;
 
@@ -58,77 +135,362 @@ fn main() -> Result<ExitCode> {
         ColorSpec::Never => colored::control::set_override(false),
     };
 
+    let worker_specs = split_workers(&args.command)?;
+    let labels = Rc::new(worker_labels(&worker_specs));
+
     // configure outputs based on cli arguments
-    let (stderr_outputs, stdout_outputs) = setup_outputs(&args);
+    let (stderr_outputs, stdout_outputs) = setup_outputs(&args, labels);
+
+    // Forwarding stdin only makes sense when there is exactly one worker to
+    // forward it to, and only when stdin is something that actually reaches
+    // EOF: forwarding an interactive terminal's stdin would block the pump
+    // thread on a keypress that may never come, even after the worker exits,
+    // since the pump has no way to abandon an in-progress read.
+    let pipe_stdin = !args.no_stdin && worker_specs.len() == 1 && !std::io::stdin().is_terminal();
+
+    let workers = worker_specs
+        .into_iter()
+        .enumerate()
+        .map(|(id, spec)| spawn_worker(id, spec, pipe_stdin))
+        .collect::<Result<Vec<_>>>()?;
+
+    let status = run(
+        workers,
+        stderr_outputs,
+        stdout_outputs,
+        args.timeout,
+        args.grace_period,
+    )?;
+
+    let code = if status.timed_out {
+        TIMEOUT_EXIT_CODE
+    } else {
+        status
+            .worker_statuses
+            .iter()
+            .find_map(|(_, exit_status)| {
+                (!exit_status.success()).then(|| exit_status.code().unwrap_or(126) as u8)
+            })
+            .unwrap_or(0)
+    };
+
+    Ok(ExitCode::from(code))
+}
+
+/// A single worker's command line, parsed from one `--`-delimited group of
+/// trailing arguments. A leading `name=` token labels the worker explicitly;
+/// otherwise it is labelled with its command.
+struct WorkerSpec {
+    name: Option<String>,
+    command: String,
+    args: Vec<String>,
+}
+
+impl WorkerSpec {
+    fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.command)
+    }
+}
+
+/// Split the trailing CLI arguments into one [`WorkerSpec`] per `--`-delimited group.
+/// With no `--` at all, the whole slice is a single worker's command line.
+fn split_workers(tokens: &[String]) -> Result<Vec<WorkerSpec>> {
+    let specs: Vec<WorkerSpec> = tokens
+        .split(|token| token == "--")
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            let (first, rest) = group.split_first().expect("empty groups are filtered out");
+
+            let (name, command) = match first.split_once('=') {
+                Some((name, command)) if !name.is_empty() => {
+                    (Some(name.to_owned()), command.to_owned())
+                }
+                _ => (None, first.to_owned()),
+            };
+
+            WorkerSpec {
+                name,
+                command,
+                args: rest.to_vec(),
+            }
+        })
+        .collect();
 
-    let child = Command::new(&args.command)
-        .args(&args.args)
+    anyhow::ensure!(!specs.is_empty(), "No worker command given");
+
+    Ok(specs)
+}
+
+/// A fixed-width, colored label identifying one worker's lines in interleaved output.
+#[derive(Debug, Clone)]
+struct WorkerLabel {
+    name: String,
+    color: colored::Color,
+}
+
+fn worker_labels(specs: &[WorkerSpec]) -> Vec<WorkerLabel> {
+    let width = specs
+        .iter()
+        .map(|spec| spec.display_name().len())
+        .max()
+        .unwrap_or(0);
+
+    specs
+        .iter()
+        .enumerate()
+        .map(|(id, spec)| WorkerLabel {
+            name: format!("{:<width$}", spec.display_name(), width = width),
+            color: WORKER_COLORS[id % WORKER_COLORS.len()],
+        })
+        .collect()
+}
+
+/// A spawned worker, identified by the index of its [`WorkerSpec`] in the original list.
+struct Worker {
+    id: usize,
+    child: std::process::Child,
+}
+
+fn spawn_worker(id: usize, spec: WorkerSpec, pipe_stdin: bool) -> Result<Worker> {
+    let child_stdin = if pipe_stdin {
+        std::process::Stdio::piped()
+    } else {
+        std::process::Stdio::null()
+    };
+
+    let child = Command::new(&spec.command)
+        .args(&spec.args)
+        .stdin(child_stdin)
         .stderr(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .spawn()
-        .context("Failed to spawn child process")?;
+        .with_context(|| format!("Failed to spawn worker command '{}'", spec.command))?;
 
-    let status = run(child, stderr_outputs, stdout_outputs)?;
+    Ok(Worker { id, child })
+}
 
-    Ok(ExitCode::from(status.code().unwrap_or(126) as u8))
+/// The outcome of [`run`]: every worker's final [`std::process::ExitStatus`]
+/// (tagged by [`Worker::id`]), plus whether any worker was forcibly killed
+/// after `--timeout` elapsed.
+struct RunStatus {
+    worker_statuses: Vec<(usize, std::process::ExitStatus)>,
+    timed_out: bool,
 }
 
-/// Attach to a [`std::process::Child`] process
-/// and write its output to `stderr_outputs` and `stdout_outputs` respectively.
-/// Each channel is read by a separate thread which send the messages
-/// via an [`std::sync::mpsc::Sender`] back to the main thread
-/// where the messages are processed in order.
+/// Supervise every [`Worker`] concurrently, merging their output into
+/// `stderr_outputs` and `stdout_outputs`. Each worker's stdout/stderr is read
+/// by its own pair of threads, which send the messages via a shared
+/// [`std::sync::mpsc::Sender`] back to the main thread where they are merged
+/// in timestamp order.
+///
+/// If `timeout` is set, one reaper thread per worker sends `SIGTERM` once the
+/// deadline elapses and escalates to `SIGKILL` after `grace_period` if that
+/// worker is still alive. Killing a worker closes its pipes, which unblocks
+/// its reader threads and lets `merge_output` make progress.
 fn run(
-    mut child: std::process::Child,
+    mut workers: Vec<Worker>,
     mut stderr_outputs: Outputs,
     mut stdout_outputs: Outputs,
-) -> Result<std::process::ExitStatus, anyhow::Error> {
-    let stderr = BufReader::new(child.stderr.take().expect("Stderr is piped"));
-    let stdout = BufReader::new(child.stdout.take().expect("Stdout is piped"));
+    timeout: Option<Timeout>,
+    grace_period: Timeout,
+) -> Result<RunStatus, anyhow::Error> {
+    let stream_count = workers.len() * 2;
 
-    let status = std::thread::scope(|scope| {
-        let (receiver, stderr_thread, stdout_thread) = setup_output_channels(scope, stderr, stdout);
+    let done: Vec<std::sync::atomic::AtomicBool> = workers
+        .iter()
+        .map(|_| std::sync::atomic::AtomicBool::new(false))
+        .collect();
+    let timed_out = std::sync::atomic::AtomicBool::new(false);
 
-        let (stdout_remaining, stderr_remaining) =
-            read_lines_with_backoff(receiver, 100, &mut stderr_outputs, &mut stdout_outputs)?;
+    let (worker_statuses, timed_out) = std::thread::scope(|scope| {
+        let (sender, receiver) = std::sync::mpsc::channel();
 
-        // Drain the remaining messages
-        drain_remaining_messages(
-            stdout_remaining,
-            stderr_remaining,
+        let mut reader_threads = Vec::with_capacity(stream_count);
+        let mut stdin_thread = None;
+        let mut reaper_threads = Vec::with_capacity(workers.len());
+
+        for worker in &mut workers {
+            let stderr = BufReader::new(worker.child.stderr.take().expect("Stderr is piped"));
+            let stdout = BufReader::new(worker.child.stdout.take().expect("Stdout is piped"));
+
+            let (stderr_thread, stdout_thread) =
+                spawn_worker_readers(scope, worker.id, stderr, stdout, sender.clone());
+            reader_threads.push(stderr_thread);
+            reader_threads.push(stdout_thread);
+
+            if let Some(child_stdin) = worker.child.stdin.take() {
+                stdin_thread = Some(spawn_stdin_pump(scope, child_stdin));
+            }
+
+            if let Some(timeout) = timeout {
+                let pid = worker.child.id();
+                let done = &done[worker.id];
+                let timed_out = &timed_out;
+                reaper_threads.push(scope.spawn(move || {
+                    reap_after_timeout(pid, timeout.0, grace_period.0, done, timed_out)
+                }));
+            }
+        }
+        drop(sender);
+
+        // Reap each worker as soon as both of its reader threads report
+        // `Closed`, rather than waiting for the whole fleet to drain: with
+        // more than one worker, a fast-exiting worker must have `done` set
+        // promptly so its own reaper thread never fires a signal at its
+        // (possibly recycled) pid while waiting on a slower sibling.
+        let mut worker_statuses = Vec::with_capacity(workers.len());
+        let remaining = merge_output(
+            receiver,
+            REORDER_WINDOW,
+            stream_count,
             &mut stderr_outputs,
             &mut stdout_outputs,
+            |worker_id| {
+                let worker = workers
+                    .iter_mut()
+                    .find(|worker| worker.id == worker_id)
+                    .expect("worker_id refers to a spawned worker");
+                let status = worker
+                    .child
+                    .wait()
+                    .with_context(|| format!("Failed to wait for worker {worker_id}"))?;
+                done[worker_id].store(true, std::sync::atomic::Ordering::SeqCst);
+                worker_statuses.push((worker_id, status));
+                anyhow::Ok(())
+            },
         )?;
 
-        // Clean up resources for child process to avoid zombies.
-        // Also, in case of errors in the reader channels,
-        // ensure that the process is finished regardless.
-        let status = child.wait().context("Failed to wait for child process")?;
+        // Drain the remaining messages
+        drain_remaining_messages(remaining, &mut stderr_outputs, &mut stdout_outputs)?;
 
         // Check for io errors in channels.
         // If channels panicked likewise panic here as there is not much we can say in that case.
-        stderr_thread
-            .join()
-            .expect("panic occured in stderr writer thread")
-            .context("Failed to write stderr output")?;
-        stdout_thread
-            .join()
-            .expect("panic occured in stdout writer thread")
-            .context("Failed to write stdout output")?;
-
-        anyhow::Ok(status)
+        for reader_thread in reader_threads {
+            reader_thread
+                .join()
+                .expect("panic occured in output reader thread")
+                .context("Failed to write output")?;
+        }
+
+        if let Some(stdin_thread) = stdin_thread {
+            stdin_thread
+                .join()
+                .expect("panic occured in stdin pump thread")
+                .context("Failed to forward stdin to child")?;
+        }
+
+        for reaper_thread in reaper_threads {
+            reaper_thread
+                .join()
+                .expect("panic occured in timeout reaper thread");
+        }
+
+        anyhow::Ok((
+            worker_statuses,
+            timed_out.load(std::sync::atomic::Ordering::SeqCst),
+        ))
     })?;
 
-    Ok(status)
+    Ok(RunStatus {
+        worker_statuses,
+        timed_out,
+    })
+}
+
+/// Poll `done` until it is set or `timeout` elapses.
+/// On timeout, send `SIGTERM` (Unix) / run `taskkill /T` (Windows),
+/// then wait up to `grace_period` longer before escalating to `SIGKILL`
+/// (Unix) / `taskkill /T /F` (Windows).
+///
+/// `done` must be set by the caller once it has reaped the child via `wait`,
+/// so this thread never signals a pid that may have been recycled by the OS.
+fn reap_after_timeout(
+    pid: u32,
+    timeout: std::time::Duration,
+    grace_period: std::time::Duration,
+    done: &std::sync::atomic::AtomicBool,
+    timed_out: &std::sync::atomic::AtomicBool,
+) {
+    use std::sync::atomic::Ordering;
+
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+    if !wait_until(done, timeout, POLL_INTERVAL) {
+        return;
+    }
+
+    timed_out.store(true, Ordering::SeqCst);
+    terminate_process(pid);
+
+    if !wait_until(done, grace_period, POLL_INTERVAL) {
+        return;
+    }
+
+    kill_process(pid);
+}
+
+/// Sleep in `poll_interval` steps until `done` is set or `timeout` elapses.
+/// Returns `true` if the timeout elapsed first, `false` if `done` was observed.
+fn wait_until(
+    done: &std::sync::atomic::AtomicBool,
+    timeout: std::time::Duration,
+    poll_interval: std::time::Duration,
+) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+
+    loop {
+        if done.load(std::sync::atomic::Ordering::SeqCst) {
+            return false;
+        }
+
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+
+        std::thread::sleep(poll_interval.min(remaining));
+    }
+}
+
+#[cfg(unix)]
+fn terminate_process(pid: u32) {
+    // SAFETY: `kill` with a valid signal is safe to call with any pid;
+    // worst case the process no longer exists and this is a no-op.
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGTERM);
+    }
+}
+
+#[cfg(unix)]
+fn kill_process(pid: u32) {
+    // SAFETY: see `terminate_process`.
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn terminate_process(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T"])
+        .output();
+}
+
+#[cfg(windows)]
+fn kill_process(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .output();
 }
 
 /// Create sets of [`OutputSpec`]s for each channel (stdout/stderr).
 /// Each will have write to the respective stdout/err
 /// of this executable as well as a common log file.
 ///
-/// Log files are always colorized except with [`Args::color`]
-/// set to [`ColorSpec::Never`].
-fn setup_outputs(args: &Args) -> (Outputs, Outputs) {
+/// The terminal is always rendered as text, colorized per [`Args::color`]; the log file is
+/// rendered by whichever [`Formatter`] [`Args::format`] selects. Text log
+/// files are always colorized except with [`Args::color`] set to [`ColorSpec::Never`].
+fn setup_outputs(args: &Args, labels: Rc<Vec<WorkerLabel>>) -> (Outputs, Outputs) {
     let start_timestamp = time::UtcDateTime::now();
 
     let log_file = open_log_file(&args.log_file).expect("Failed to open log file");
@@ -139,20 +501,43 @@ fn setup_outputs(args: &Args) -> (Outputs, Outputs) {
         ColorSpec::Always | ColorSpec::Never => args.color,
     };
 
+    // Only bother labelling lines when there is more than one worker to tell apart.
+    let print_label = labels.len() > 1;
+
+    let logfile_formatter = |highlight: colored::Color| -> Box<dyn Formatter> {
+        match args.format {
+            FormatSpec::Text => Box::new(TextFormatter {
+                start_timestamp,
+                print_timestamp: args.timestamps,
+                print_label,
+                worker_labels: labels.clone(),
+                color: OutputColor(logfile_color_spec, highlight),
+            }),
+            FormatSpec::Json => Box::new(JsonFormatter {
+                worker_labels: labels.clone(),
+            }),
+            FormatSpec::Logfmt => Box::new(LogfmtFormatter {
+                worker_labels: labels.clone(),
+            }),
+        }
+    };
+
     let stderr_outputs = Outputs(vec![
         // terminal output
         OutputSpec {
             output: Box::new(std::io::stderr()),
-            start_timestamp,
-            print_timestamp: args.timestamps,
-            color: OutputColor(args.color, ERROR_COLOR),
+            formatter: Box::new(TextFormatter {
+                start_timestamp,
+                print_timestamp: args.timestamps,
+                print_label,
+                worker_labels: labels.clone(),
+                color: OutputColor(args.color, ERROR_COLOR),
+            }),
         },
         // log file output
         OutputSpec {
             output: Box::new(share_log_file.clone()),
-            start_timestamp,
-            print_timestamp: args.timestamps,
-            color: OutputColor(logfile_color_spec, ERROR_COLOR),
+            formatter: logfile_formatter(ERROR_COLOR),
         },
     ]);
 
@@ -160,148 +545,289 @@ fn setup_outputs(args: &Args) -> (Outputs, Outputs) {
         // terminal output
         OutputSpec {
             output: Box::new(std::io::stdout()),
-            print_timestamp: args.timestamps,
-            start_timestamp,
-            color: OutputColor(args.color, DEFAULT_COLOR),
+            formatter: Box::new(TextFormatter {
+                start_timestamp,
+                print_timestamp: args.timestamps,
+                print_label,
+                worker_labels: labels.clone(),
+                color: OutputColor(args.color, DEFAULT_COLOR),
+            }),
         },
         // log file output
         OutputSpec {
             output: Box::new(share_log_file.clone()),
-            start_timestamp,
-            print_timestamp: args.timestamps,
-            color: OutputColor(logfile_color_spec, DEFAULT_COLOR),
+            formatter: logfile_formatter(DEFAULT_COLOR),
         },
     ]);
     (stderr_outputs, stdout_outputs)
 }
 
+/// How old a buffered message may get while held back for an empty peer queue
+/// before we stop treating that queue as a potential source of an even-older
+/// message and let the buffered message through.
+const REORDER_WINDOW: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Merge every worker-stream's messages into `stderr_outputs`/`stdout_outputs`
+/// in the order they actually occurred, using each [`Message`]'s monotonic
+/// [`std::time::Instant`] rather than its wall-clock timestamp: the wall clock
+/// can jump, but `Instant` can only move forward, so it is the only sound way
+/// to decide which of two concurrently-produced messages came first.
+///
+/// A message is held back only while some other, not-yet-drained stream is
+/// still empty and the message is younger than [`REORDER_WINDOW`] -- giving
+/// that stream a chance to deliver a message that should have preceded it.
+/// Once a stream's reader thread reports it closed (see [`Event::Closed`]),
+/// it can no longer produce an earlier message, so it stops blocking the merge.
+///
+/// `on_worker_drained` is called with a worker's id as soon as *both* of its
+/// streams have reported closed, i.e. as soon as it is known that worker can
+/// no longer produce output -- the caller uses this to reap that worker
+/// immediately instead of waiting for every other worker to finish too.
 #[must_use]
-fn read_lines_with_backoff(
-    receiver: std::sync::mpsc::Receiver<(Source, Message)>,
-    backoff_ms: usize,
+fn merge_output(
+    receiver: std::sync::mpsc::Receiver<Event>,
+    reorder_window: std::time::Duration,
+    stream_count: usize,
     stderr_outputs: &mut Outputs,
     stdout_outputs: &mut Outputs,
-) -> Result<(VecDeque<Message>, VecDeque<Message>), anyhow::Error> {
-    let mut stdout_queue = VecDeque::new();
-    let mut stderr_queue = VecDeque::new();
-
-    for line in receiver.iter() {
-        match line {
-            (Source::Stdout, message) => stdout_queue.push_back(message),
-            (Source::Stderr, message) => stderr_queue.push_back(message),
-        }
+    mut on_worker_drained: impl FnMut(usize) -> Result<(), anyhow::Error>,
+) -> Result<Vec<VecDeque<Message>>, anyhow::Error> {
+    let mut queues: Vec<VecDeque<Message>> = (0..stream_count).map(|_| VecDeque::new()).collect();
+    let mut drained = vec![false; stream_count];
+    let mut closed_streams = vec![0u8; stream_count / 2];
 
-        // Drain the messages if they are older than 100ms to allow for delay.
-        // Question: is this even necessary? MPSC channels are already ordered,
-        // and the delay between taking the time and sending the message
-        // is minimal.
-        loop {
-            match (stdout_queue.front(), stderr_queue.front()) {
-                (None, None) => {
-                    break;
-                }
-                (Some(stdout), _)
-                    if (time::UtcDateTime::now() - stdout.timestamp).whole_milliseconds()
-                        < backoff_ms as i128 =>
-                {
-                    break;
-                }
-                (_, Some(stderr))
-                    if (time::UtcDateTime::now() - stderr.timestamp).whole_milliseconds()
-                        < backoff_ms as i128 =>
-                {
-                    break;
-                }
-                (Some(_), None) => {
-                    stdout_outputs.write_message(&stdout_queue.pop_front().unwrap())?
-                }
-                (None, Some(_)) => {
-                    stderr_outputs.write_message(&stderr_queue.pop_front().unwrap())?
-                }
-                (Some(stdout), Some(stderr)) => {
-                    if stdout.timestamp < stderr.timestamp {
-                        stdout_outputs.write_message(&stdout_queue.pop_front().unwrap())?;
-                    } else {
-                        stderr_outputs.write_message(&stderr_queue.pop_front().unwrap())?;
-                    }
+    loop {
+        // Wait no longer than `reorder_window`, so a held-back message whose
+        // window elapses gets released even if no *new* event ever arrives to
+        // prompt a re-check -- otherwise a silent stream could stall a chatty
+        // one indefinitely, which is exactly what `ready_stream` is meant to
+        // prevent.
+        match receiver.recv_timeout(reorder_window) {
+            Ok(Event::Message(message)) => {
+                queues[stream_index(message.worker_id, message.stream)].push_back(message);
+            }
+            Ok(Event::Closed(index)) => {
+                drained[index] = true;
+
+                let worker_id = index / 2;
+                closed_streams[worker_id] += 1;
+                if closed_streams[worker_id] == 2 {
+                    on_worker_drained(worker_id)?;
                 }
-            };
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
         }
+
+        while let Some(index) = ready_stream(&queues, &drained, reorder_window) {
+            let message = queues[index].pop_front().unwrap();
+            write_message_for_stream(&message, stderr_outputs, stdout_outputs)?;
+        }
+    }
+
+    Ok(queues)
+}
+
+fn stream_index(worker_id: usize, stream: Stream) -> usize {
+    worker_id * 2 + usize::from(matches!(stream, Stream::Stderr))
+}
+
+/// Pick the worker-stream whose head message is safe to emit next: the one
+/// with the globally-oldest `Instant`, unless some other, non-drained stream
+/// is still empty and the candidate hasn't yet sat long enough to rule out
+/// that stream delivering something older.
+fn ready_stream(
+    queues: &[VecDeque<Message>],
+    drained: &[bool],
+    reorder_window: std::time::Duration,
+) -> Option<usize> {
+    let (index, oldest) = queues
+        .iter()
+        .enumerate()
+        .filter_map(|(i, queue)| queue.front().map(|message| (i, message.instant)))
+        .min_by_key(|&(_, instant)| instant)?;
+
+    let blocked_by_silent_peer =
+        queues
+            .iter()
+            .zip(drained)
+            .enumerate()
+            .any(|(peer_index, (peer_queue, &peer_drained))| {
+                peer_index != index
+                    && peer_queue.is_empty()
+                    && !peer_drained
+                    && oldest.elapsed() < reorder_window
+            });
+
+    if blocked_by_silent_peer {
+        None
+    } else {
+        Some(index)
     }
+}
+
+fn oldest_nonempty_queue(queues: &[VecDeque<Message>]) -> Option<usize> {
+    queues
+        .iter()
+        .enumerate()
+        .filter_map(|(index, queue)| queue.front().map(|message| (index, message.instant)))
+        .min_by_key(|&(_, instant)| instant)
+        .map(|(index, _)| index)
+}
 
-    Ok((stdout_queue, stderr_queue))
+fn write_message_for_stream(
+    message: &Message,
+    stderr_outputs: &mut Outputs,
+    stdout_outputs: &mut Outputs,
+) -> Result<()> {
+    match message.stream {
+        Stream::Stdout => stdout_outputs.write_message(message),
+        Stream::Stderr => stderr_outputs.write_message(message),
+    }
 }
 
 fn drain_remaining_messages(
-    mut stdout_remaining: VecDeque<Message>,
-    mut stderr_remaining: VecDeque<Message>,
+    mut queues: Vec<VecDeque<Message>>,
     stderr_outputs: &mut Outputs,
     stdout_outputs: &mut Outputs,
 ) -> Result<(), anyhow::Error> {
-    Ok(loop {
-        match (stdout_remaining.front(), stderr_remaining.front()) {
-            (None, None) => break,
-            (Some(_), None) => {
-                stdout_outputs.write_message(&stdout_remaining.pop_front().unwrap())?;
-            }
-            (None, Some(_)) => {
-                stderr_outputs.write_message(&stderr_remaining.pop_front().unwrap())?;
-            }
-            (Some(stdout), Some(stderr)) => {
-                if stdout.timestamp < stderr.timestamp {
-                    stdout_outputs.write_message(&stdout_remaining.pop_front().unwrap())?;
-                } else {
-                    stderr_outputs.write_message(&stderr_remaining.pop_front().unwrap())?;
-                }
-            }
-        }
-    })
+    while let Some(index) = oldest_nonempty_queue(&queues) {
+        let message = queues[index].pop_front().unwrap();
+        write_message_for_stream(&message, stderr_outputs, stdout_outputs)?;
+    }
+    Ok(())
 }
 
 struct Message {
-    // [sic] System time is not monotonic
+    /// Wall-clock time, used only for display (absolute/relative `--ts`).
     timestamp: time::UtcDateTime,
+    /// Monotonic time, used solely to order messages across worker-streams:
+    /// [`time::UtcDateTime`] [sic] is not monotonic and can jump, which would
+    /// otherwise corrupt the interleaving.
+    instant: std::time::Instant,
+    worker_id: usize,
+    stream: Stream,
     line: String,
 }
 
 impl Message {
-    fn record(line: String) -> Self {
+    fn record(worker_id: usize, stream: Stream, line: String) -> Self {
         Self {
             timestamp: time::UtcDateTime::now(),
+            instant: std::time::Instant::now(),
+            worker_id,
+            stream,
             line,
         }
     }
 }
 
-enum Source {
+#[derive(Debug, Clone, Copy)]
+enum Stream {
     Stderr,
     Stdout,
 }
 
-fn setup_output_channels<'scope>(
+/// An item sent from a worker-stream reader thread to the merge loop:
+/// either a line of output, or notice that the stream has no more to send.
+enum Event {
+    Message(Message),
+    /// The reader thread for the worker-stream at this index has finished;
+    /// that stream can no longer deliver an earlier message, so the merge
+    /// no longer needs to wait on it.
+    Closed(usize),
+}
+
+/// Spawn the stderr/stdout reader threads for a single worker,
+/// tagging every message they send with `worker_id` so the merge
+/// in [`merge_output`] can attribute it back to its source, and
+/// reporting closure once each stream's reader thread is done.
+fn spawn_worker_readers<'scope>(
     scope: &'scope std::thread::Scope<'scope, '_>,
+    worker_id: usize,
     stderr: impl BufRead + Send + 'scope,
     stdout: impl BufRead + Send + 'scope,
+    sender: std::sync::mpsc::Sender<Event>,
 ) -> (
-    std::sync::mpsc::Receiver<(Source, Message)>,
     std::thread::ScopedJoinHandle<'scope, std::result::Result<(), anyhow::Error>>,
     std::thread::ScopedJoinHandle<'scope, std::result::Result<(), anyhow::Error>>,
 ) {
-    let (sender, receiver) = std::sync::mpsc::channel();
     let stderr_sender = sender.clone();
     let stderr_thread = scope.spawn(move || {
-        redirect(stderr, |message| {
-            stderr_sender.send((Source::Stderr, message))
-        })
+        let result = redirect(stderr, |line| {
+            stderr_sender.send(Event::Message(Message::record(
+                worker_id,
+                Stream::Stderr,
+                line,
+            )))
+        });
+        let _ = stderr_sender.send(Event::Closed(stream_index(worker_id, Stream::Stderr)));
+        result
     });
 
     let stdout_sender = sender;
     let stdout_thread = scope.spawn(move || {
-        redirect(stdout, |message| {
-            stdout_sender.send((Source::Stdout, message))
-        })
+        let result = redirect(stdout, |line| {
+            stdout_sender.send(Event::Message(Message::record(
+                worker_id,
+                Stream::Stdout,
+                line,
+            )))
+        });
+        let _ = stdout_sender.send(Event::Closed(stream_index(worker_id, Stream::Stdout)));
+        result
     });
-    (receiver, stderr_thread, stdout_thread)
+    (stderr_thread, stdout_thread)
+}
+
+/// Pump this process's stdin into the child's stdin on a dedicated thread.
+///
+/// This must run concurrently with the stdout/stderr reader threads rather than
+/// inline: if we blocked the main thread writing to the child's stdin while the
+/// child was blocked writing to its (unread) stdout, both sides would deadlock
+/// on each other's full pipe buffer.
+///
+/// Reads and forwards raw bytes rather than lines: stdin may carry arbitrary
+/// binary data (e.g. `t3 log.txt grep foo` in a pipeline), and decoding it as
+/// UTF-8 text would turn any non-UTF-8 byte into a hard error.
+///
+/// A write that fails because the child has already exited and closed its end
+/// of the pipe is treated as reaching our own EOF, not as a hard failure.
+///
+/// Only spawned when stdin isn't a terminal (see [`std::io::IsTerminal`]):
+/// a blocking read on an interactive terminal has no way to be abandoned once
+/// the child exits, which would otherwise hang `t3` until the user pressed
+/// Enter or Ctrl-D.
+fn spawn_stdin_pump<'scope>(
+    scope: &'scope std::thread::Scope<'scope, '_>,
+    mut child_stdin: std::process::ChildStdin,
+) -> std::thread::ScopedJoinHandle<'scope, Result<(), anyhow::Error>> {
+    scope.spawn(move || {
+        let stdin = std::io::stdin();
+        let mut stdin = stdin.lock();
+
+        loop {
+            let buf = stdin.fill_buf().context("Failed to read stdin")?;
+            if buf.is_empty() {
+                break;
+            }
+            let bytes_read = buf.len();
+
+            match child_stdin
+                .write_all(buf)
+                .and_then(|()| child_stdin.flush())
+            {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => break,
+                Err(err) => return Err(err).context("Failed to write to child stdin"),
+            }
+
+            stdin.consume(bytes_read);
+        }
+
+        // Dropping `child_stdin` here closes the pipe, signalling EOF to the child.
+        Ok(())
+    })
 }
 
 struct OutputColor(ColorSpec, colored::Color);
@@ -310,17 +836,29 @@ trait WriteMessage {
     fn write_message(&mut self, message: &Message) -> Result<()>;
 }
 
-struct OutputSpec {
-    output: Box<dyn Write>,
+/// Render a [`Message`] into the line that gets written to an [`OutputSpec`]'s sink.
+///
+/// This is the "how" layered on top of [`Message`]'s "what stream + when":
+/// the same merged stream of messages can be rendered as colorized text for
+/// a terminal and as machine-parseable JSON Lines for a log file, without
+/// either sink knowing about the other.
+trait Formatter {
+    fn format(&self, message: &Message) -> String;
+}
+
+/// Colorized, optionally timestamped and worker-labelled plain text.
+/// This is the formatter used for the terminal, and the default for log files.
+struct TextFormatter {
     start_timestamp: time::UtcDateTime,
     print_timestamp: TimestampSpec,
+    print_label: bool,
+    worker_labels: Rc<Vec<WorkerLabel>>,
     color: OutputColor,
 }
 
-impl WriteMessage for OutputSpec {
-    /// Write messages according to the spec.
-    fn write_message(&mut self, message: &Message) -> Result<()> {
-        let line = {
+impl Formatter for TextFormatter {
+    fn format(&self, message: &Message) -> String {
+        let (label, line) = {
             let OutputColor(spec, color) = self.color;
 
             // Enable or disable color based on the associated color spec.
@@ -331,7 +869,14 @@ impl WriteMessage for OutputSpec {
                 ColorSpec::Never => colored::control::set_override(false),
             }
 
-            message.line.color(color).to_string()
+            let label = self.print_label.then(|| {
+                let worker_label = &self.worker_labels[message.worker_id];
+                worker_label.name.color(worker_label.color).to_string()
+            });
+
+            let line = message.line.color(color).to_string();
+
+            (label, line)
         };
 
         let timestamp = match self.print_timestamp {
@@ -352,15 +897,109 @@ impl WriteMessage for OutputSpec {
             _ => None,
         };
 
-        if let Some(timestamp_str) = timestamp {
-            let timestamp_str = timestamp_str.color(TIME_COLOR).to_string();
-
-            writeln!(self.output, "{timestamp_str}: {line}",)?;
-        } else {
-            writeln!(self.output, "{line}")?;
+        let rendered = match (timestamp, label) {
+            (Some(timestamp), Some(label)) => {
+                let timestamp = timestamp.color(TIME_COLOR).to_string();
+                format!("{timestamp}: {label} {line}")
+            }
+            (Some(timestamp), None) => {
+                let timestamp = timestamp.color(TIME_COLOR).to_string();
+                format!("{timestamp}: {line}")
+            }
+            (None, Some(label)) => format!("{label} {line}"),
+            (None, None) => line,
         };
 
         colored::control::unset_override();
+        rendered
+    }
+}
+
+/// One JSON object per line: `{"ts":"...","stream":"stdout","worker":"...","line":"..."}`.
+/// Never colorized, so it stays machine-parseable regardless of [`Args::color`].
+struct JsonFormatter {
+    worker_labels: Rc<Vec<WorkerLabel>>,
+}
+
+impl Formatter for JsonFormatter {
+    fn format(&self, message: &Message) -> String {
+        format!(
+            r#"{{"ts":"{}","stream":"{}","worker":{},"line":{}}}"#,
+            message.timestamp,
+            stream_name(message.stream),
+            json_string(self.worker_labels[message.worker_id].name.trim()),
+            json_string(&message.line),
+        )
+    }
+}
+
+/// `ts=... stream=stdout worker=... line=...`, with `worker` and `line`
+/// quoted by [`logfmt_value`] when needed. Never colorized.
+struct LogfmtFormatter {
+    worker_labels: Rc<Vec<WorkerLabel>>,
+}
+
+impl Formatter for LogfmtFormatter {
+    fn format(&self, message: &Message) -> String {
+        format!(
+            "ts={} stream={} worker={} line={}",
+            message.timestamp,
+            stream_name(message.stream),
+            logfmt_value(self.worker_labels[message.worker_id].name.trim()),
+            logfmt_value(&message.line),
+        )
+    }
+}
+
+fn stream_name(stream: Stream) -> &'static str {
+    match stream {
+        Stream::Stdout => "stdout",
+        Stream::Stderr => "stderr",
+    }
+}
+
+/// Minimal JSON string escaping, avoiding a dependency on a full JSON library
+/// for the handful of control characters that can appear in a line of output.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render a logfmt value, quoting it if it is empty or contains whitespace,
+/// quotes or `=`.
+fn logfmt_value(value: &str) -> String {
+    if value.is_empty()
+        || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=')
+    {
+        format!("{:?}", value)
+    } else {
+        value.to_owned()
+    }
+}
+
+struct OutputSpec {
+    output: Box<dyn Write>,
+    formatter: Box<dyn Formatter>,
+}
+
+impl WriteMessage for OutputSpec {
+    /// Write messages according to the spec.
+    fn write_message(&mut self, message: &Message) -> Result<()> {
+        let line = self.formatter.format(message);
+        writeln!(self.output, "{line}")?;
         Ok(())
     }
 }
@@ -439,19 +1078,165 @@ fn open_log_file<'a>(path: impl AsRef<Path>) -> Result<impl Write + 'a> {
         .context("Failed to open log file")
 }
 
-/// Read lines from a [BufRead] instance,
-/// create a [Message] from each read line
-/// and then call the supplied closure with this message.
+/// Read lines from a [BufRead] instance
+/// and call the supplied closure with each line.
 fn redirect<E: Into<anyhow::Error>>(
     reader: impl BufRead,
-    message_handler: impl Fn(Message) -> Result<(), E>,
+    message_handler: impl Fn(String) -> Result<(), E>,
 ) -> Result<()> {
     for line in reader.lines() {
         let line = line.context("Failed to read line")?;
-        let message = Message::record(line);
-        message_handler(message)
+        message_handler(line)
             .map_err(Into::into)
             .context("Failed to write line to output")?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn timeout_parses_each_unit() {
+        assert_eq!(Timeout::from_str("30s").unwrap().0.as_secs(), 30);
+        assert_eq!(Timeout::from_str("2m").unwrap().0.as_secs(), 120);
+        assert_eq!(Timeout::from_str("1h").unwrap().0.as_secs(), 3600);
+    }
+
+    #[test]
+    fn timeout_rejects_missing_or_unknown_unit() {
+        assert!(Timeout::from_str("30").is_err());
+        assert!(Timeout::from_str("30x").is_err());
+        assert!(Timeout::from_str("s").is_err());
+    }
+
+    #[test]
+    fn split_workers_with_no_separator_is_a_single_worker() {
+        let tokens = vec!["echo".to_owned(), "hi".to_owned()];
+        let specs = split_workers(&tokens).unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].command, "echo");
+        assert_eq!(specs[0].args, vec!["hi".to_owned()]);
+        assert_eq!(specs[0].name, None);
+    }
+
+    #[test]
+    fn split_workers_splits_on_separator_and_parses_names() {
+        let tokens = vec![
+            "build=make".to_owned(),
+            "all".to_owned(),
+            "--".to_owned(),
+            "watch".to_owned(),
+        ];
+        let specs = split_workers(&tokens).unwrap();
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].name, Some("build".to_owned()));
+        assert_eq!(specs[0].command, "make");
+        assert_eq!(specs[0].args, vec!["all".to_owned()]);
+        assert_eq!(specs[1].name, None);
+        assert_eq!(specs[1].command, "watch");
+    }
+
+    #[test]
+    fn split_workers_rejects_empty_input() {
+        assert!(split_workers(&[]).is_err());
+    }
+
+    #[test]
+    fn worker_labels_are_padded_to_the_widest_name() {
+        let specs = vec![
+            WorkerSpec {
+                name: Some("a".to_owned()),
+                command: "x".to_owned(),
+                args: vec![],
+            },
+            WorkerSpec {
+                name: Some("bb".to_owned()),
+                command: "y".to_owned(),
+                args: vec![],
+            },
+        ];
+        let labels = worker_labels(&specs);
+        assert_eq!(labels[0].name, "a ");
+        assert_eq!(labels[1].name, "bb");
+    }
+
+    #[test]
+    fn json_string_escapes_control_characters() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a\"b"), "\"a\\\"b\"");
+        assert_eq!(json_string("a\\b"), "\"a\\\\b\"");
+        assert_eq!(json_string("a\nb\tc"), "\"a\\nb\\tc\"");
+    }
+
+    #[test]
+    fn logfmt_value_quotes_only_when_needed() {
+        assert_eq!(logfmt_value("plain"), "plain");
+        assert_eq!(logfmt_value(""), "\"\"");
+        assert_eq!(logfmt_value("has space"), "\"has space\"");
+        assert_eq!(logfmt_value("has=equals"), "\"has=equals\"");
+    }
+
+    fn message_at(worker_id: usize, instant: std::time::Instant) -> Message {
+        Message {
+            timestamp: time::UtcDateTime::now(),
+            instant,
+            worker_id,
+            stream: Stream::Stdout,
+            line: String::new(),
+        }
+    }
+
+    #[test]
+    fn ready_stream_prefers_the_globally_oldest_message() {
+        let now = std::time::Instant::now();
+        let mut queues = vec![VecDeque::new(), VecDeque::new()];
+        queues[0].push_back(message_at(0, now));
+        queues[1].push_back(message_at(
+            1,
+            now.checked_sub(std::time::Duration::from_millis(200))
+                .unwrap(),
+        ));
+        let drained = vec![true, true];
+
+        let index = ready_stream(&queues, &drained, std::time::Duration::from_millis(100));
+        assert_eq!(index, Some(1));
+    }
+
+    #[test]
+    fn ready_stream_holds_back_a_young_message_while_a_peer_is_silent() {
+        let now = std::time::Instant::now();
+        let mut queues = vec![VecDeque::new(), VecDeque::new()];
+        queues[0].push_back(message_at(0, now));
+        let drained = vec![false, false];
+
+        let index = ready_stream(&queues, &drained, std::time::Duration::from_millis(100));
+        assert_eq!(index, None);
+    }
+
+    #[test]
+    fn ready_stream_releases_once_the_reorder_window_elapses() {
+        let old = std::time::Instant::now()
+            .checked_sub(std::time::Duration::from_millis(200))
+            .unwrap();
+        let mut queues = vec![VecDeque::new(), VecDeque::new()];
+        queues[0].push_back(message_at(0, old));
+        let drained = vec![false, false];
+
+        let index = ready_stream(&queues, &drained, std::time::Duration::from_millis(100));
+        assert_eq!(index, Some(0));
+    }
+
+    #[test]
+    fn ready_stream_is_not_blocked_by_a_drained_peer() {
+        let now = std::time::Instant::now();
+        let mut queues = vec![VecDeque::new(), VecDeque::new()];
+        queues[0].push_back(message_at(0, now));
+        let drained = vec![false, true];
+
+        let index = ready_stream(&queues, &drained, std::time::Duration::from_millis(100));
+        assert_eq!(index, Some(0));
+    }
+}